@@ -1,192 +1,1111 @@
 use rand::Rng;
+use std::alloc::{self, Layout};
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering as AtomicOrdering};
+use std::sync::Mutex;
 
-// Define the maximum number of levels the Skip List can have.
+// Hard ceiling on the number of levels the Skip List can have, regardless of
+// `p` or the expected size passed to `with_params`. The sentinel head is
+// always allocated this wide so it can act as the predecessor at any level a
+// list ever grows into; ordinary nodes are allocated to their own height.
 const MAX_LEVEL: usize = 16;
-// Probability of promoting a node to the next level (p=0.5)
+// Default promotion probability (p=0.5) used by `SkipList::new`.
 const P: f64 = 0.5;
 
 // A single node in the Skip List.
-// It uses raw pointers for the `next` array, which is typical for
-// performance-critical, linked-style structures in Rust when
-// managed pointers (like `Box` or `Rc`) lead to complexity or overhead.
-// We use `unsafe` blocks appropriately for pointer manipulation.
-struct Node {
-    value: i32,
-    // An array of raw pointers, one for each level.
-    next: [Option<*mut Node>; MAX_LEVEL],
+//
+// Only `key`, `value` and `height` live in the struct itself. The node's
+// tower — `height` `next` pointers immediately followed by `height` `width`
+// counters — is appended after the struct in a single allocation sized to
+// exactly `height`, instead of a fixed `MAX_LEVEL`-wide array. Since most
+// nodes are promoted to only one or two levels, this avoids wasting most of
+// the tower space that a fixed-width array would allocate for them.
+//
+// `next` entries are `AtomicPtr` (null meaning "no successor") so that
+// readers can traverse the list with acquire-loads while a writer links new
+// nodes in with release-stores, without either side taking a lock. `width`
+// entries are `AtomicUsize` for the same reason — a reader may observe a
+// slightly stale count, which only affects the precision of `rank`/
+// `get_index`, never memory safety.
+//
+// `key` is `None` only for the sentinel head node, which never holds data.
+struct Node<K, V> {
+    key: Option<K>,
+    value: Option<V>,
+    height: usize,
 }
 
-impl Node {
-    // Creates a new node with a given value and specific level count.
-    fn new(value: i32, _level: usize) -> Node {
-        // Initialise all `next` pointers up to `level` to `None`.
-        Node {
-            value,
-            next: [None; MAX_LEVEL],
+impl<K, V> Node<K, V> {
+    // Computes the layout of a node's full allocation (header + tower) for a
+    // given `height`, along with the byte offsets of the trailing `next` and
+    // `width` arrays within it.
+    fn tower_layout(height: usize) -> (Layout, usize, usize) {
+        let header = Layout::new::<Node<K, V>>();
+        let next_array =
+            Layout::array::<AtomicPtr<Node<K, V>>>(height).expect("tower layout overflow");
+        let (with_next, next_offset) = header.extend(next_array).expect("tower layout overflow");
+        let width_array = Layout::array::<AtomicUsize>(height).expect("tower layout overflow");
+        let (full, width_offset) = with_next.extend(width_array).expect("tower layout overflow");
+        (full.pad_to_align(), next_offset, width_offset)
+    }
+
+    // Allocates a node with its tower sized to exactly `height` slots, every
+    // `next` pointer starting out null and every `width` starting at 1.
+    unsafe fn alloc(key: Option<K>, value: Option<V>, height: usize) -> *mut Node<K, V> {
+        let (layout, next_offset, width_offset) = Self::tower_layout(height);
+        let raw = alloc::alloc(layout);
+        if raw.is_null() {
+            alloc::handle_alloc_error(layout);
+        }
+
+        let node_ptr = raw as *mut Node<K, V>;
+        node_ptr.write(Node { key, value, height });
+
+        let next_ptr = raw.add(next_offset) as *mut AtomicPtr<Node<K, V>>;
+        let width_ptr = raw.add(width_offset) as *mut AtomicUsize;
+        for i in 0..height {
+            next_ptr.add(i).write(AtomicPtr::new(ptr::null_mut()));
+            width_ptr.add(i).write(AtomicUsize::new(1));
+        }
+
+        node_ptr
+    }
+
+    // Drops a node's tower allocation. Callers must guarantee no reader can
+    // still be traversing into this node — see `SkipList`'s epoch-style
+    // reclamation via `pin`/`Guard`.
+    //
+    // `value_already_taken` is set for a node retired by `insert`'s
+    // duplicate-key path, whose `value` was already moved out into the
+    // caller's returned `Option<V>`; dropping it here too would double-drop
+    // it, so only `key` is dropped in that case.
+    unsafe fn dealloc(ptr: *mut Node<K, V>, value_already_taken: bool) {
+        let height = (*ptr).height;
+        let (layout, _, _) = Self::tower_layout(height);
+        if value_already_taken {
+            ptr::drop_in_place(&mut (*ptr).key as *mut Option<K>);
+        } else {
+            ptr::drop_in_place(ptr);
         }
+        alloc::dealloc(ptr as *mut u8, layout);
+    }
+
+    // Unchecked pointer-offset helper into the trailing `next` array.
+    // Callers must ensure `level < height`.
+    unsafe fn next_slot(ptr: *mut Node<K, V>, level: usize) -> *mut AtomicPtr<Node<K, V>> {
+        let (_, next_offset, _) = Self::tower_layout((*ptr).height);
+        (ptr as *mut u8)
+            .add(next_offset)
+            .cast::<AtomicPtr<Node<K, V>>>()
+            .add(level)
+    }
+
+    // Unchecked pointer-offset helper into the trailing `width` array.
+    // Callers must ensure `level < height`.
+    unsafe fn width_slot(ptr: *mut Node<K, V>, level: usize) -> *mut AtomicUsize {
+        let (_, _, width_offset) = Self::tower_layout((*ptr).height);
+        (ptr as *mut u8).add(width_offset).cast::<AtomicUsize>().add(level)
+    }
+
+    /// Acquire-load of `next[level]`. Null means "no successor". Safe to call
+    /// concurrently with a writer publishing new nodes via `set_next`.
+    unsafe fn next(ptr: *mut Node<K, V>, level: usize) -> *mut Node<K, V> {
+        (*Self::next_slot(ptr, level)).load(AtomicOrdering::Acquire)
+    }
+
+    /// Release-store of `next[level]`, publishing everything written to the
+    /// new node before this call to any reader that acquire-loads it.
+    unsafe fn set_next(ptr: *mut Node<K, V>, level: usize, next: *mut Node<K, V>) {
+        (*Self::next_slot(ptr, level)).store(next, AtomicOrdering::Release);
     }
+
+    /// Links a node in at `level` only if `next[level]` still equals
+    /// `current`, so a concurrent writer touching the same predecessor can't
+    /// silently clobber this link.
+    unsafe fn cas_next(
+        ptr: *mut Node<K, V>,
+        level: usize,
+        current: *mut Node<K, V>,
+        new: *mut Node<K, V>,
+    ) -> bool {
+        (*Self::next_slot(ptr, level))
+            .compare_exchange(current, new, AtomicOrdering::Release, AtomicOrdering::Acquire)
+            .is_ok()
+    }
+
+    unsafe fn width(ptr: *mut Node<K, V>, level: usize) -> usize {
+        (*Self::width_slot(ptr, level)).load(AtomicOrdering::Relaxed)
+    }
+
+    unsafe fn set_width(ptr: *mut Node<K, V>, level: usize, width: usize) {
+        (*Self::width_slot(ptr, level)).store(width, AtomicOrdering::Relaxed);
+    }
+}
+
+// The main Skip List structure, an ordered map keyed by `K`.
+//
+// Readers (`get`, `iter`, `range`, `rank`, `get_index`) never block: they
+// only ever acquire-load `next` pointers, mirroring the LevelDB/RocksDB
+// memtable model. They're private, reachable only through a pinned
+// [`Guard`] (see `pin`), because `remove` never frees a node immediately —
+// it logically unlinks it, then parks it in `retired` until
+// `active_readers` drops to zero. A `Guard` holds `active_readers` above
+// zero for its whole lifetime, and every reference or iterator it hands out
+// borrows from the `Guard` itself rather than from the list, so the borrow
+// checker ties their lifetime to the pin that keeps their nodes alive.
+// Reading without a `Guard` would let a concurrent `remove` reclaim a node
+// out from under an in-progress traversal.
+//
+// Note: `iter`/`range` (and `get`/`rank`/`get_index`) were originally added
+// as `pub fn`s directly on `SkipList`. Gating them behind `Guard` narrows
+// that public surface; it's an intentional consequence of the concurrent-
+// reads safety fix above, not an incidental API change.
+//
+// `insert` and `remove` also take `&self` so the list can live behind an
+// `Arc` and be shared with reader threads, but serialize with each other
+// through `writer_lock`, so there is still only one writer in flight at a
+// time. Each link or unlink in `insert`/`remove` is a single compare-exchange
+// that is expected to succeed because `writer_lock` is held; a failed
+// compare-exchange is not retried, so writers are not lock-free with respect
+// to each other.
+pub struct SkipList<K: Ord, V> {
+    head: *mut Node<K, V>,   // A pointer to the sentinel head node (no data).
+    level: AtomicUsize,      // Current maximum level of the entire list.
+    len: AtomicUsize,        // Number of entries currently stored.
+    p: f64,                  // Promotion probability used by `random_level`.
+    max_level: usize,        // Effective cap on `level`, at most `MAX_LEVEL - 1`.
+    writer_lock: Mutex<()>,  // Serializes `insert`/`remove` calls.
+    active_readers: AtomicUsize, // Number of currently-pinned `Guard`s.
+    // Logically-removed nodes awaiting reclamation, each tagged with whether
+    // its `value` was already moved out (see `Node::dealloc`).
+    retired: Mutex<Vec<(*mut Node<K, V>, bool)>>,
 }
 
-// The main Skip List structure.
-pub struct SkipList {
-    head: Option<*mut Node>, // A pointer to the sentinel head node (no data).
-    level: usize,            // Current maximum level of the entire list.
+// SAFETY: all shared mutable state is behind atomics or `Mutex`; the raw
+// pointers themselves are never aliased mutably outside of those.
+unsafe impl<K: Ord + Send, V: Send> Send for SkipList<K, V> {}
+// SAFETY: a pinned `Guard` hands out `&K`/`&V` to every thread that pins the
+// list concurrently, so both `K` and `V` must also be `Sync` — not just
+// `Send` — or callers could read a non-`Sync` type like `Cell` from multiple
+// threads at once with no synchronization between them.
+unsafe impl<K: Ord + Send + Sync, V: Send + Sync> Sync for SkipList<K, V> {}
+
+impl<K: Ord, V> SkipList<K, V> {
+    pub fn new() -> SkipList<K, V> {
+        // Create the sentinel head node. It has no key/value and MAX_LEVEL next pointers.
+        let head_node = unsafe { Node::alloc(None, None, MAX_LEVEL) };
+        SkipList {
+            head: head_node,
+            level: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+            p: P,
+            max_level: MAX_LEVEL - 1,
+            writer_lock: Mutex::new(()),
+            active_readers: AtomicUsize::new(0),
+            retired: Mutex::new(Vec::new()),
+        }
+    }
 }
 
-impl SkipList {
-    pub fn new() -> SkipList {
-        // Create the sentinel head node. It has a dummy value (-1) and MAX_LEVEL next pointers.
-        let head_node = Box::into_raw(Box::new(Node::new(-1, MAX_LEVEL)));
+impl<K: Ord, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    /// Creates a list tuned for `expected_n` entries at promotion probability
+    /// `p`. The effective max level is `ceil(log(expected_n) / log(1/p))`,
+    /// clamped to `MAX_LEVEL - 1`, so towers aren't grown taller than the
+    /// list is ever likely to need. Lowering `p` below the default 0.5 trades
+    /// a modest amount of search speed for fewer pointers per node, as in
+    /// Pugh's original analysis.
+    ///
+    /// `p` must be in `(0, 1)`, as assumed by both the level formula above
+    /// and `random_level`'s `rng < p` promotion test.
+    pub fn with_params(p: f64, expected_n: usize) -> SkipList<K, V> {
+        debug_assert!(p > 0.0 && p < 1.0, "p must be in (0, 1), got {p}");
+        let head_node = unsafe { Node::alloc(None, None, MAX_LEVEL) };
         SkipList {
-            head: Some(head_node),
-            level: 0,
+            head: head_node,
+            level: AtomicUsize::new(0),
+            len: AtomicUsize::new(0),
+            p,
+            max_level: Self::effective_max_level(p, expected_n),
+            writer_lock: Mutex::new(()),
+            active_readers: AtomicUsize::new(0),
+            retired: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Computes `ceil(log(expected_n) / log(1/p))`, clamped to at least 1
+    /// level and at most `MAX_LEVEL - 1`.
+    fn effective_max_level(p: f64, expected_n: usize) -> usize {
+        let n = expected_n.max(2) as f64;
+        let level = (n.ln() / (1.0_f64 / p).ln()).ceil();
+        (level.max(1.0) as usize).min(MAX_LEVEL - 1)
+    }
+
+    /// Returns the number of entries currently stored.
+    pub fn len(&self) -> usize {
+        self.len.load(AtomicOrdering::Acquire)
+    }
+
+    /// Returns `true` if the list holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn level(&self) -> usize {
+        self.level.load(AtomicOrdering::Acquire)
+    }
+
+    /// Pins the current thread as a reader. While the returned [`Guard`] is
+    /// alive, any node unlinked by a concurrent `remove` is kept around
+    /// instead of freed, so references handed out while pinned stay valid
+    /// for the guard's lifetime.
+    pub fn pin(&self) -> Guard<'_, K, V> {
+        self.active_readers.fetch_add(1, AtomicOrdering::AcqRel);
+        Guard { list: self }
+    }
+
+    /// Frees any retired nodes, but only once no reader is pinned.
+    fn try_reclaim(&self) {
+        if self.active_readers.load(AtomicOrdering::Acquire) != 0 {
+            return;
+        }
+        let mut retired = self.retired.lock().unwrap();
+        for (node_ptr, value_already_taken) in retired.drain(..) {
+            unsafe {
+                Node::dealloc(node_ptr, value_already_taken);
+            }
         }
     }
 
-    /// Inserts a value into the Skip List.
-    pub fn insert(&mut self, value: i32) {
-        let mut update: [Option<*mut Node>; MAX_LEVEL] = [None; MAX_LEVEL];
+    /// Inserts a key-value pair into the Skip List. If the key already exists,
+    /// its value is replaced and the old value is returned.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let _writer = self.writer_lock.lock().unwrap();
+
+        let mut update: [*mut Node<K, V>; MAX_LEVEL] = [ptr::null_mut(); MAX_LEVEL];
+        // `rank[i]` accumulates the base-level distance travelled from the
+        // head down to `update[i]`, so the new node's own widths can be
+        // derived without a second traversal.
+        let mut rank: [usize; MAX_LEVEL] = [0; MAX_LEVEL];
+        let level = self.level();
         let mut current = self.head;
 
         // 1. Find the insertion point at all levels
-        for i in (0..=self.level).rev() {
+        for i in (0..=level).rev() {
+            rank[i] = if i == level { 0 } else { rank[i + 1] };
             unsafe {
-                while let Some(node_ptr) = current {
-                    let node = &mut *node_ptr;
-                    // Move right if the next node exists and its value is less than the new value.
-                    if node.next[i].is_some() && (*node.next[i].unwrap()).value < value {
-                        current = node.next[i];
-                    } else {
+                loop {
+                    let next_ptr = Node::next(current, i);
+                    if next_ptr.is_null() || (*next_ptr).key.as_ref().unwrap().cmp(&key) != Ordering::Less {
                         break;
                     }
+                    rank[i] += Node::width(current, i);
+                    current = next_ptr;
                 }
                 // Record the predecessor at this level (where insertion happens)
                 update[i] = current;
             }
         }
-        
-        // At this point, `current` is the node before the insertion point on level 0.
-        // We ensure we are at level 0 by getting the first `next` of the final `current`.
-        current = Some(update[0].unwrap());
 
-        // Check for duplicates (optional, for a Set-like list)
+        // Check for a duplicate key. A pinned reader could be mid-read of
+        // this node's value with no lock held, so we never mutate it in
+        // place; instead we publish the replacement as a brand-new node at
+        // the same tower position (same `next` pointers, same widths), via
+        // the same release-ordered CAS swap `remove` uses to unlink a node,
+        // then retire the old one. A reader only ever sees a fully formed
+        // old node or a fully formed new one, never a torn write.
         unsafe {
-            if current.is_some() {
-                let node = &*current.unwrap();
-                if node.next[0].is_some() && (*node.next[0].unwrap()).value == value {
-                    // Value already exists, do nothing
-                    return;
+            let existing_ptr = Node::next(update[0], 0);
+            if !existing_ptr.is_null() && (*existing_ptr).key.as_ref().unwrap().cmp(&key) == Ordering::Equal {
+                let height = (*existing_ptr).height;
+                let replacement_ptr = Node::alloc(Some(key), Some(value), height);
+                // `i` is also the tower level passed to `Node::width`/
+                // `set_width`/`next`/`set_next`/`cas_next`, not just an
+                // index into `update`, so it can't be replaced by an
+                // iterator over `update` alone.
+                #[allow(clippy::needless_range_loop)]
+                for i in 0..height {
+                    Node::set_next(replacement_ptr, i, Node::next(existing_ptr, i));
+                    Node::set_width(replacement_ptr, i, Node::width(existing_ptr, i));
+                    let linked = Node::cas_next(update[i], i, existing_ptr, replacement_ptr);
+                    debug_assert!(linked, "writer_lock should prevent concurrent tower edits");
                 }
+
+                // Safe to read without taking a lock: up to this point
+                // `existing_ptr`'s value has never been mutated, only read,
+                // so this is a plain copy, not a race with a concurrent
+                // reader. The copy left behind is never dropped — see
+                // `Node::dealloc`'s `value_already_taken` flag below.
+                let old_value = ptr::read(&(*existing_ptr).value);
+                self.retired.lock().unwrap().push((existing_ptr, true));
+                self.try_reclaim();
+                return old_value;
             }
         }
 
         // 2. Determine the new node's level
-        let new_level = Self::random_level();
+        let new_level = self.random_level();
 
         // 3. Update the list's max level if necessary
-        if new_level > self.level {
-            // Update the `update` array for the new levels to point to the head node
-            for i in (self.level + 1)..=new_level {
+        let mut effective_level = level;
+        if new_level > level {
+            // Update the `update` array for the new levels to point to the head node.
+            // The head spans the whole list at these previously-unused levels.
+            for i in (level + 1)..=new_level {
+                rank[i] = 0;
                 update[i] = self.head;
+                unsafe {
+                    Node::set_width(self.head, i, self.len());
+                }
             }
-            self.level = new_level;
+            effective_level = new_level;
+            self.level.store(new_level, AtomicOrdering::Release);
         }
 
-        // 4. Create and link the new node
-        let new_node_ptr = Box::into_raw(Box::new(Node::new(value, new_level + 1)));
+        // 4. Create and link the new node, with a tower sized to exactly `new_level + 1`.
+        let new_node_ptr = unsafe { Node::alloc(Some(key), Some(value), new_level + 1) };
 
-        // Link the new node into the list from level 0 up to `new_level`
+        // Link the new node into the list from level 0 up to `new_level`, splitting
+        // each predecessor's width across the new node according to how far into
+        // it the new node falls. Bottom-up, release-ordered CAS publishes the
+        // fully-initialised node to readers one level at a time; no other
+        // writer can be racing on the same predecessor since `writer_lock` is
+        // held, so each compare-exchange is expected to succeed on the first try.
+        //
+        // `i` doubles as both the index into `update`/`rank` and the tower
+        // level passed to `Node::width`/`set_width`/`cas_next`, so it can't
+        // be replaced by an iterator over `update` alone.
+        #[allow(clippy::needless_range_loop)]
         for i in 0..=new_level {
             unsafe {
-                let predecessor_ptr = update[i].unwrap();
-                let predecessor = &mut *predecessor_ptr;
+                let predecessor_ptr = update[i];
+                let predecessor_width = Node::width(predecessor_ptr, i);
+                let old_next = Node::next(predecessor_ptr, i);
+
+                Node::set_next(new_node_ptr, i, old_next);
+                Node::set_width(new_node_ptr, i, predecessor_width - (rank[0] - rank[i]));
+
+                let linked = Node::cas_next(predecessor_ptr, i, old_next, new_node_ptr);
+                debug_assert!(linked, "writer_lock should prevent concurrent tower edits");
+                Node::set_width(predecessor_ptr, i, (rank[0] - rank[i]) + 1);
+            }
+        }
 
-                // New node's next pointer points to the old successor
-                (*new_node_ptr).next[i] = predecessor.next[i];
-                // Predecessor's next pointer points to the new node
-                predecessor.next[i] = Some(new_node_ptr);
+        // Levels above the new node's height still gained one more node below them.
+        //
+        // `i` is also the tower level passed to `Node::width`/`set_width`,
+        // not just an index into `update`, so it can't be replaced by an
+        // iterator over `update` alone.
+        #[allow(clippy::needless_range_loop)]
+        for i in (new_level + 1)..=effective_level {
+            unsafe {
+                let predecessor_ptr = update[i];
+                let width = Node::width(predecessor_ptr, i);
+                Node::set_width(predecessor_ptr, i, width + 1);
             }
         }
+
+        self.len.fetch_add(1, AtomicOrdering::Release);
+        None
     }
 
-    /// Searches for a value in the Skip List. Returns true if found.
-    pub fn search(&self, value: i32) -> bool {
+    /// Returns a reference to the value associated with `key`, if present.
+    /// Lock-free: only ever acquire-loads `next` pointers.
+    ///
+    /// Not `pub`: a node a concurrent `remove` unlinked could be reclaimed by
+    /// `try_reclaim` the instant `active_readers` drops to zero, so every
+    /// read has to go through a held [`Guard`], which is what keeps
+    /// `active_readers` above zero for the life of the returned reference.
+    /// See [`SkipList::pin`].
+    fn get(&self, key: &K) -> Option<&V> {
         let mut current = self.head;
 
         // Start from the highest current level and work down
-        for i in (0..=self.level).rev() {
+        for i in (0..=self.level()).rev() {
             unsafe {
-                while let Some(node_ptr) = current {
-                    let node = &*node_ptr;
-                    // Move right if the next node exists and its value is less than the search value.
-                    if node.next[i].is_some() && (*node.next[i].unwrap()).value < value {
-                        current = node.next[i];
-                    } else {
-                        break; // Drop down to the next level
+                loop {
+                    let next_ptr = Node::next(current, i);
+                    if next_ptr.is_null() || (*next_ptr).key.as_ref().unwrap().cmp(key) != Ordering::Less {
+                        break;
                     }
+                    current = next_ptr;
                 }
             }
         }
-        
+
         // After the loops, `current` should be the node before the potential match on level 0.
         // We now check the node immediately after `current` on level 0.
         unsafe {
-            if let Some(node_ptr) = current {
-                let node = &*node_ptr;
-                if let Some(next_ptr) = node.next[0] {
-                    // Check if the value of the next node matches
-                    return (*next_ptr).value == value;
+            let next_ptr = Node::next(current, 0);
+            if !next_ptr.is_null() {
+                let next = &*next_ptr;
+                if next.key.as_ref().unwrap().cmp(key) == Ordering::Equal {
+                    return next.value.as_ref();
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns a mutable reference to the value associated with `key`, if present.
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut current = self.head;
+
+        for i in (0..=self.level()).rev() {
+            unsafe {
+                loop {
+                    let next_ptr = Node::next(current, i);
+                    if next_ptr.is_null() || (*next_ptr).key.as_ref().unwrap().cmp(key) != Ordering::Less {
+                        break;
+                    }
+                    current = next_ptr;
+                }
+            }
+        }
+
+        unsafe {
+            let next_ptr = Node::next(current, 0);
+            if !next_ptr.is_null() {
+                let next = &mut *next_ptr;
+                if next.key.as_ref().unwrap().cmp(key) == Ordering::Equal {
+                    return next.value.as_mut();
+                }
+            }
+        }
+        None
+    }
+
+    /// Removes `key` from the Skip List. Returns `true` if a node was removed.
+    ///
+    /// The node is only logically unlinked here; it's physically freed once
+    /// `active_readers` reaches zero, so a reader already past this point in
+    /// its traversal can still safely follow the node's old `next` pointers.
+    pub fn remove(&self, key: &K) -> bool {
+        let _writer = self.writer_lock.lock().unwrap();
+
+        let mut update: [*mut Node<K, V>; MAX_LEVEL] = [ptr::null_mut(); MAX_LEVEL];
+        let level = self.level();
+        let mut current = self.head;
+
+        // 1. Find the predecessor of the target key at every level
+        for i in (0..=level).rev() {
+            unsafe {
+                loop {
+                    let next_ptr = Node::next(current, i);
+                    if next_ptr.is_null() || (*next_ptr).key.as_ref().unwrap().cmp(key) != Ordering::Less {
+                        break;
+                    }
+                    current = next_ptr;
+                }
+                // Record the predecessor at this level
+                update[i] = current;
+            }
+        }
+
+        // 2. Check whether the node immediately after `update[0]` is the target
+        let target_ptr = unsafe { Node::next(update[0], 0) };
+        let found = unsafe { !target_ptr.is_null() && (*target_ptr).key.as_ref().unwrap().cmp(key) == Ordering::Equal };
+        if !found {
+            return false;
+        }
+
+        // 3. Splice the target out at every level, folding its width into the
+        // predecessor at levels where it was linked, or simply shrinking the
+        // predecessor's span by one node everywhere else.
+        //
+        // `i` is also the tower level passed to `Node::width`/`set_width`/
+        // `next`/`cas_next`, not just an index into `update`, so it can't be
+        // replaced by an iterator over `update` alone.
+        #[allow(clippy::needless_range_loop)]
+        for i in 0..=level {
+            unsafe {
+                let predecessor_ptr = update[i];
+                if Node::next(predecessor_ptr, i) == target_ptr {
+                    let merged = Node::width(predecessor_ptr, i) + Node::width(target_ptr, i) - 1;
+                    Node::set_width(predecessor_ptr, i, merged);
+                    Node::cas_next(predecessor_ptr, i, target_ptr, Node::next(target_ptr, i));
+                } else {
+                    let shrunk = Node::width(predecessor_ptr, i) - 1;
+                    Node::set_width(predecessor_ptr, i, shrunk);
+                }
+            }
+        }
+
+        // 4. Park the removed node instead of freeing it immediately, so any
+        // reader still traversing through it is unaffected. Its value was
+        // never touched, so it's still there to drop normally on reclaim.
+        self.retired.lock().unwrap().push((target_ptr, false));
+
+        // 5. Shrink the list's level while the top levels have gone empty
+        unsafe {
+            let mut shrunk_level = level;
+            while shrunk_level > 0 && Node::next(self.head, shrunk_level).is_null() {
+                shrunk_level -= 1;
+            }
+            if shrunk_level != level {
+                self.level.store(shrunk_level, AtomicOrdering::Release);
+            }
+        }
+
+        self.len.fetch_sub(1, AtomicOrdering::Release);
+        self.try_reclaim();
+        true
+    }
+
+    /// Returns the entry at the given 0-based position in ascending key
+    /// order, or `None` if `index` is out of bounds.
+    ///
+    /// Not `pub` — only reachable through a held [`Guard`]; see [`SkipList::get`].
+    fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        if index >= self.len() {
+            return None;
+        }
+        let target = index + 1;
+        let mut traversed = 0;
+        let mut current = self.head;
+
+        for i in (0..=self.level()).rev() {
+            unsafe {
+                loop {
+                    let next_ptr = Node::next(current, i);
+                    if next_ptr.is_null() || traversed + Node::width(current, i) > target {
+                        break;
+                    }
+                    traversed += Node::width(current, i);
+                    current = next_ptr;
+                }
+            }
+        }
+
+        unsafe {
+            if current.is_null() {
+                None
+            } else {
+                let node = &*current;
+                Some((node.key.as_ref().unwrap(), node.value.as_ref().unwrap()))
+            }
+        }
+    }
+
+    /// Returns the 0-based position of `key` in ascending key order, or
+    /// `None` if it isn't present.
+    ///
+    /// Not `pub` — only reachable through a held [`Guard`]; see [`SkipList::get`].
+    fn rank(&self, key: &K) -> Option<usize> {
+        let mut traversed = 0;
+        let mut current = self.head;
+
+        for i in (0..=self.level()).rev() {
+            unsafe {
+                loop {
+                    let next_ptr = Node::next(current, i);
+                    if next_ptr.is_null() || (*next_ptr).key.as_ref().unwrap().cmp(key) != Ordering::Less {
+                        break;
+                    }
+                    traversed += Node::width(current, i);
+                    current = next_ptr;
+                }
+            }
+        }
+
+        unsafe {
+            let next_ptr = Node::next(current, 0);
+            if !next_ptr.is_null() {
+                let next = &*next_ptr;
+                if next.key.as_ref().unwrap().cmp(key) == Ordering::Equal {
+                    return Some(traversed + Node::width(current, 0) - 1);
+                }
+            }
+        }
+        None
+    }
+
+    /// Returns an iterator over all entries in ascending key order, by walking
+    /// the fully-ordered level-0 list.
+    ///
+    /// Not `pub` — only reachable through a held [`Guard`]; see [`SkipList::get`].
+    fn iter(&self) -> Iter<'_, K, V> {
+        let current = unsafe { Node::next(self.head, 0) };
+        Iter {
+            current,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the entries whose keys fall within `bounds`,
+    /// in ascending key order.
+    ///
+    /// The tower is descended once to position the cursor just before the
+    /// lower bound in O(log n), then the remainder of the scan walks
+    /// `next[0]` until the upper bound is exceeded.
+    ///
+    /// Not `pub` — only reachable through a held [`Guard`]; see [`SkipList::get`].
+    fn range<R: RangeBounds<K>>(&self, bounds: R) -> Range<'_, K, V, R> {
+        let mut current = self.head;
+
+        for i in (0..=self.level()).rev() {
+            unsafe {
+                loop {
+                    let next_ptr = Node::next(current, i);
+                    if next_ptr.is_null() {
+                        break;
+                    }
+                    let next_key = (*next_ptr).key.as_ref().unwrap();
+                    let before_start = match bounds.start_bound() {
+                        Bound::Included(k) => next_key < k,
+                        Bound::Excluded(k) => next_key <= k,
+                        Bound::Unbounded => false,
+                    };
+                    if !before_start {
+                        break;
+                    }
+                    current = next_ptr;
                 }
             }
         }
-        false
+
+        // `current` is the last node strictly before the lower bound, so the
+        // scan starts at its level-0 successor.
+        let start = unsafe { Node::next(current, 0) };
+        Range {
+            current: start,
+            bounds,
+            _marker: PhantomData,
+        }
     }
 
     /// Generates a random level for a new node.
-    /// The probability of increasing the level is P (0.5).
-    fn random_level() -> usize {
+    /// The probability of increasing the level is `self.p`.
+    fn random_level(&self) -> usize {
         let mut lvl = 0;
         let mut rng = rand::rng();
 
         // Keep incrementing the level as long as a random number
-        // is less than the probability P, up to MAX_LEVEL.
-        while rng.random::<f64>() < P && lvl < MAX_LEVEL - 1 {
+        // is less than the probability `p`, up to this list's max level.
+        while rng.random::<f64>() < self.p && lvl < self.max_level {
             lvl += 1;
         }
         lvl
     }
 }
-    
-impl Drop for SkipList {
+
+impl<K: Ord, V> Drop for SkipList<K, V> {
     fn drop(&mut self) {
+        // No reader can observe the list past this point, so every retired
+        // node can be freed unconditionally.
+        for (node_ptr, value_already_taken) in self.retired.lock().unwrap().drain(..) {
+            unsafe {
+                Node::dealloc(node_ptr, value_already_taken);
+            }
+        }
+
         let mut current = self.head;
-        // Traverse only the base list (level 0) to free all nodes.
-        while let Some(node_ptr) = current {
+        // Traverse only the base list (level 0) to free all remaining live nodes.
+        while !current.is_null() {
             unsafe {
                 // Get the next pointer at level 0 before deallocating the current node.
-                let next = (*node_ptr).next[0];
-
-                // Take ownership of the raw pointer and drop the Box, which deallocates the Node.
-                let _ = Box::from_raw(node_ptr);
+                let next = Node::next(current, 0);
+                Node::dealloc(current, false);
                 current = next;
             }
         }
     }
 }
 
+/// A pinned reader handle. While alive, nodes unlinked by a concurrent
+/// `remove` are kept around instead of freed — see [`SkipList::pin`].
+pub struct Guard<'a, K: Ord, V> {
+    list: &'a SkipList<K, V>,
+}
+
+impl<'a, K: Ord, V> Drop for Guard<'a, K, V> {
+    fn drop(&mut self) {
+        self.list.active_readers.fetch_sub(1, AtomicOrdering::AcqRel);
+        self.list.try_reclaim();
+    }
+}
+
+impl<'a, K: Ord, V> Guard<'a, K, V> {
+    /// Reads through the pinned list — see [`SkipList::get`].
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.list.get(key)
+    }
+
+    /// Reads through the pinned list — see [`SkipList::get_index`].
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.list.get_index(index)
+    }
+
+    /// Reads through the pinned list — see [`SkipList::rank`].
+    pub fn rank(&self, key: &K) -> Option<usize> {
+        self.list.rank(key)
+    }
+
+    /// Reads through the pinned list — see [`SkipList::iter`].
+    ///
+    /// The returned iterator borrows from this guard rather than from the
+    /// list directly, so it can't outlive the pin that keeps its nodes alive.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.list.iter()
+    }
+
+    /// Reads through the pinned list — see [`SkipList::range`].
+    ///
+    /// The returned iterator borrows from this guard rather than from the
+    /// list directly, so it can't outlive the pin that keeps its nodes alive.
+    pub fn range<R: RangeBounds<K>>(&self, bounds: R) -> Range<'_, K, V, R> {
+        self.list.range(bounds)
+    }
+}
+
+/// An iterator over all entries of a [`SkipList`] in ascending key order.
+pub struct Iter<'a, K: Ord, V> {
+    current: *mut Node<K, V>,
+    _marker: PhantomData<&'a SkipList<K, V>>,
+}
+
+impl<'a, K: Ord, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current.is_null() {
+            return None;
+        }
+        let node = unsafe { &*self.current };
+        self.current = unsafe { Node::next(self.current, 0) };
+        Some((node.key.as_ref().unwrap(), node.value.as_ref().unwrap()))
+    }
+}
+
+/// An iterator over the entries of a [`SkipList`] whose keys fall within a
+/// given range, in ascending key order.
+pub struct Range<'a, K: Ord, V, R: RangeBounds<K>> {
+    current: *mut Node<K, V>,
+    bounds: R,
+    _marker: PhantomData<&'a SkipList<K, V>>,
+}
+
+impl<'a, K: Ord, V, R: RangeBounds<K>> Iterator for Range<'a, K, V, R> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current.is_null() {
+            return None;
+        }
+        let node = unsafe { &*self.current };
+        let key = node.key.as_ref().unwrap();
+        if !self.bounds.contains(key) {
+            self.current = ptr::null_mut();
+            return None;
+        }
+        self.current = unsafe { Node::next(self.current, 0) };
+        Some((key, node.value.as_ref().unwrap()))
+    }
+}
+
 fn main() {
-    let mut skip_list = SkipList::new();
-    let values = vec![3, 6, 9, 2, 11, 1, 4];
-    
-    println!("Inserting values: {:?}", values);
-    for &val in &values {
-        skip_list.insert(val);
+    let skip_list = SkipList::new();
+    let entries = vec![(3, "c"), (6, "f"), (9, "i"), (2, "b"), (11, "k"), (1, "a"), (4, "d")];
+
+    println!("Inserting entries: {:?}", entries);
+    for (key, value) in entries {
+        skip_list.insert(key, value);
+    }
+
+    println!("\n--- Get Results ---");
+    let search_keys = vec![4, 5, 11, 0];
+    {
+        let guard = skip_list.pin();
+        for &key in &search_keys {
+            println!("Get {}: {:?}", key, guard.get(&key));
+        }
+        // Output should be: Some("d"), None, Some("k"), None
     }
 
-    println!("\n--- Search Results ---");
-    let search_values = vec![4, 5, 11, 0];
-    for &val in &search_values {
-        println!("Search for {}: {}", val, skip_list.search(val));
+    println!("\n--- Remove Results ---");
+    println!("Remove 9: {}", skip_list.remove(&9));
+    println!("Remove 9 again: {}", skip_list.remove(&9));
+    {
+        let guard = skip_list.pin();
+        println!("Get 9 after removal: {:?}", guard.get(&9));
+
+        println!("\n--- Iteration ---");
+        for (key, value) in guard.iter() {
+            println!("{}: {}", key, value);
+        }
+
+        println!("\n--- Range Scan (2..=6) ---");
+        for (key, value) in guard.range(2..=6) {
+            println!("{}: {}", key, value);
+        }
+
+        println!("\n--- Indexed Access ---");
+        println!("len: {}", skip_list.len());
+        for index in 0..skip_list.len() {
+            println!("get_index({}): {:?}", index, guard.get_index(index));
+        }
+        println!("rank(&6): {:?}", guard.rank(&6));
     }
-    // Output should be: true, false, true, false
-}
\ No newline at end of file
+
+    println!("\n--- Tuned Construction ---");
+    let tuned: SkipList<i32, &str> = SkipList::with_params(0.25, 1_000);
+    tuned.insert(1, "one");
+    tuned.insert(2, "two");
+    println!("Get 2 from tuned list: {:?}", tuned.pin().get(&2));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize as DropAtomicUsize, Ordering as DropOrdering};
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn remove_unlinks_key_and_reports_presence() {
+        let list = SkipList::new();
+        for key in [3, 1, 4, 5, 9, 2, 6] {
+            list.insert(key, key * 10);
+        }
+        let len_before = list.len();
+
+        assert!(list.remove(&9));
+        assert!(!list.remove(&9));
+        assert_eq!(list.len(), len_before - 1);
+        assert_eq!(list.pin().get(&9), None);
+    }
+
+    #[test]
+    fn generic_over_non_i32_key_and_value_types() {
+        let list: SkipList<String, Vec<u8>> = SkipList::new();
+        list.insert("banana".to_string(), vec![1, 2, 3]);
+        list.insert("apple".to_string(), vec![4, 5]);
+
+        let guard = list.pin();
+        assert_eq!(guard.get(&"apple".to_string()), Some(&vec![4, 5]));
+        assert_eq!(guard.get(&"banana".to_string()), Some(&vec![1, 2, 3]));
+        assert_eq!(guard.get(&"cherry".to_string()), None);
+
+        let replaced = list.insert("apple".to_string(), vec![9]);
+        assert_eq!(replaced, Some(vec![4, 5]));
+        assert_eq!(list.pin().get(&"apple".to_string()), Some(&vec![9]));
+    }
+
+    #[test]
+    fn iter_yields_all_entries_in_ascending_key_order() {
+        let list = SkipList::new();
+        for key in [5, 3, 8, 1, 9, 2] {
+            list.insert(key, key * 10);
+        }
+
+        let guard = list.pin();
+        let collected: Vec<(i32, i32)> = guard.iter().map(|(k, v)| (*k, *v)).collect();
+        assert_eq!(collected, vec![(1, 10), (2, 20), (3, 30), (5, 50), (8, 80), (9, 90)]);
+    }
+
+    #[test]
+    fn range_bounds_are_respected_inclusively_and_exclusively() {
+        let list = SkipList::new();
+        for key in 0..10 {
+            list.insert(key, key);
+        }
+
+        let guard = list.pin();
+        assert_eq!(
+            guard.range(2..=5).map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![2, 3, 4, 5],
+            "inclusive upper bound must include the endpoint"
+        );
+        assert_eq!(
+            guard.range(2..5).map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![2, 3, 4],
+            "exclusive upper bound must omit the endpoint"
+        );
+        assert_eq!(
+            guard.range(..3).map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![0, 1, 2],
+            "unbounded start must scan from the first entry"
+        );
+        assert_eq!(
+            guard.range(7..).map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![7, 8, 9],
+            "unbounded end must scan to the last entry"
+        );
+    }
+
+    #[test]
+    fn get_index_and_rank_agree_with_a_known_ascending_sequence() {
+        let list = SkipList::new();
+        // Insert out of order so `rank`/`get_index` can't accidentally pass
+        // by relying on insertion order instead of the list's own ordering.
+        for key in [40, 10, 30, 0, 20] {
+            list.insert(key, key.to_string());
+        }
+
+        let guard = list.pin();
+        let expected: Vec<i32> = vec![0, 10, 20, 30, 40];
+        for (index, key) in expected.iter().enumerate() {
+            assert_eq!(guard.get_index(index), Some((key, &key.to_string())));
+            assert_eq!(guard.rank(key), Some(index));
+        }
+
+        assert_eq!(guard.get_index(expected.len()), None, "out-of-bounds index must return None");
+        assert_eq!(guard.rank(&15), None, "absent key must return None");
+    }
+
+    #[test]
+    fn with_params_caps_effective_max_level_at_max_level_minus_one() {
+        // A huge `expected_n` would otherwise push `ceil(log(n) / log(1/p))`
+        // well past `MAX_LEVEL - 1`.
+        let list: SkipList<i32, i32> = SkipList::with_params(0.5, usize::MAX);
+        assert_eq!(list.max_level, MAX_LEVEL - 1);
+
+        // A small `expected_n` should clamp to at least one level.
+        let list: SkipList<i32, i32> = SkipList::with_params(0.5, 1);
+        assert_eq!(list.max_level, 1);
+    }
+
+    #[test]
+    fn with_params_list_still_behaves_as_an_ordered_map() {
+        let list = SkipList::with_params(0.25, 1_000);
+        for key in [5, 1, 4, 2, 3] {
+            list.insert(key, key * 100);
+        }
+
+        let guard = list.pin();
+        assert_eq!(guard.iter().map(|(k, _)| *k).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(guard.get(&3), Some(&300));
+    }
+
+    #[test]
+    fn tower_layout_sizes_next_and_width_arrays_to_the_requested_height() {
+        for height in [1, 2, 4, 16] {
+            let (layout, next_offset, width_offset) = Node::<i32, i32>::tower_layout(height);
+            assert!(
+                next_offset >= std::mem::size_of::<Node<i32, i32>>(),
+                "next array must start after the node header"
+            );
+            assert!(
+                width_offset >= next_offset + height * std::mem::size_of::<AtomicPtr<Node<i32, i32>>>(),
+                "width array must start after the full next array"
+            );
+            assert!(
+                layout.size() >= width_offset + height * std::mem::size_of::<AtomicUsize>(),
+                "allocation must be large enough to hold both trailing arrays"
+            );
+        }
+    }
+
+    #[test]
+    fn nodes_with_many_different_heights_survive_insert_remove_and_drop() {
+        // Exercises `Node::alloc`/`dealloc` across a wide spread of tower
+        // heights, since a fixed `p` mostly promotes to 1-2 levels and this
+        // wants to also hit the taller, rarer towers.
+        let list = SkipList::with_params(0.9, 2_000);
+        for key in 0..2_000 {
+            list.insert(key, key);
+        }
+        assert_eq!(list.len(), 2_000);
+
+        for key in (0..2_000).step_by(3) {
+            assert!(list.remove(&key));
+        }
+        assert_eq!(list.len(), 2_000 - (2_000usize.div_ceil(3)));
+
+        let guard = list.pin();
+        for key in 0..2_000 {
+            let expected = if key % 3 == 0 { None } else { Some(&key) };
+            assert_eq!(guard.get(&key), expected);
+        }
+        drop(guard);
+        drop(list);
+    }
+
+    // A value type that records how many times it's been dropped, so
+    // `insert`'s duplicate-key replace path can be checked for the
+    // double-drop/no-drop bug `7ef83e4` fixed once already.
+    struct DropCounted(Arc<DropAtomicUsize>);
+    impl Drop for DropCounted {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, DropOrdering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn duplicate_key_insert_drops_old_value_exactly_once() {
+        let drops = Arc::new(DropAtomicUsize::new(0));
+        let list = SkipList::new();
+
+        let old = list.insert(1, DropCounted(drops.clone()));
+        assert!(old.is_none());
+
+        let replaced = list.insert(1, DropCounted(drops.clone()));
+        assert!(replaced.is_some());
+        assert_eq!(drops.load(DropOrdering::SeqCst), 0, "old value must not be dropped before the caller drops it");
+        drop(replaced);
+        assert_eq!(drops.load(DropOrdering::SeqCst), 1, "old value must be dropped exactly once");
+
+        drop(list);
+        assert_eq!(drops.load(DropOrdering::SeqCst), 2, "remaining value must be dropped exactly once when the list drops");
+    }
+
+    #[test]
+    fn concurrent_readers_observe_a_consistent_list_during_writes() {
+        let list = Arc::new(SkipList::new());
+        for key in 0..500 {
+            list.insert(key, key);
+        }
+
+        let writer_list = list.clone();
+        let writer = thread::spawn(move || {
+            for key in 0..500 {
+                if key % 2 == 0 {
+                    writer_list.remove(&key);
+                }
+            }
+            for key in 500..750 {
+                writer_list.insert(key, key);
+            }
+        });
+
+        let mut readers = Vec::new();
+        for _ in 0..4 {
+            let reader_list = list.clone();
+            readers.push(thread::spawn(move || {
+                for _ in 0..100 {
+                    let guard = reader_list.pin();
+                    let mut last_key = None;
+                    for (key, value) in guard.iter() {
+                        assert_eq!(key, value, "a pinned reader must never see a torn write");
+                        if let Some(last) = last_key {
+                            assert!(*key > last, "iteration must stay sorted under concurrent writes");
+                        }
+                        last_key = Some(*key);
+                    }
+                }
+            }));
+        }
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        let guard = list.pin();
+        for key in 0..500 {
+            let expected = if key % 2 == 0 { None } else { Some(&key) };
+            assert_eq!(guard.get(&key), expected);
+        }
+        for key in 500..750 {
+            assert_eq!(guard.get(&key), Some(&key));
+        }
+    }
+}